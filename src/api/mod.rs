@@ -0,0 +1,2 @@
+/// Version 1 of the JSON API, consumed by `cargo` itself and by the web front-end.
+pub mod v1;
@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+
+use diesel::prelude::*;
+use json::json;
+use semver::Version;
+use tide::{Request, Response};
+
+use crate::db::models::CrateRegistration;
+use crate::db::schema::*;
+use crate::error::{AlexError, Error};
+use crate::index::{CrateVersion, Dependency, Indexer};
+use crate::utils;
+use crate::State;
+
+/// Per-version metadata bundled into the crate-info response.
+#[derive(Debug, Clone, serde::Serialize)]
+struct CrateVersionMetadata {
+    /// The version string.
+    vers: Version,
+    /// The version's declared features.
+    features: HashMap<String, Vec<String>>,
+    /// The version's dependencies.
+    dependencies: Vec<Dependency>,
+    /// Whether this version has been yanked.
+    yanked: bool,
+}
+
+impl From<CrateVersion> for CrateVersionMetadata {
+    fn from(record: CrateVersion) -> Self {
+        CrateVersionMetadata {
+            vers: record.vers,
+            features: record.features,
+            dependencies: record.deps,
+            yanked: record.yanked.unwrap_or(false),
+        }
+    }
+}
+
+/// Route: `GET /api/v1/crates/<name>/info`.
+///
+/// Bundles a crate's description, latest README, download count and the full list of version
+/// records (each as a [`CrateVersionMetadata`]) into a single JSON response, so a front-end can
+/// render a crate page in one round trip instead of scraping the rendered `index` template.
+pub async fn get(req: Request<State>) -> Result<Response, Error> {
+    let name = req.param::<String>("name").unwrap_or_default();
+
+    let state = req.state();
+    let conn = state.db.get()?;
+
+    let krate = crates::table
+        .filter(crates::name.eq(name.as_str()))
+        .first::<CrateRegistration>(&conn)
+        .optional()?
+        .ok_or_else(|| AlexError::CrateNotFound { name: name.clone() })?;
+
+    let versions = state
+        .index
+        .all_records(&name)?
+        .into_iter()
+        .map(CrateVersionMetadata::from)
+        .collect::<Vec<_>>();
+
+    Ok(utils::response::json(&json!({
+        "name": krate.name,
+        "description": krate.description,
+        "readme": krate.readme,
+        "downloads": krate.downloads,
+        "versions": versions,
+    })))
+}
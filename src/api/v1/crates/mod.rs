@@ -0,0 +1,8 @@
+/// `GET /api/v1/crates/<name>/info`: a single JSON bundle of a crate's metadata and versions.
+pub mod info;
+/// `PUT /api/v1/crates/<name>/owners` and `DELETE /api/v1/crates/<name>/owners/<author_id>`: owner management.
+pub mod owners;
+/// `PUT /api/v1/crates/new`: publishes a new crate version.
+pub mod publish;
+/// `DELETE .../yank` and `PUT .../unyank`: yanking and un-yanking crate versions.
+pub mod yank;
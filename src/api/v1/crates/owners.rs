@@ -0,0 +1,95 @@
+use diesel::prelude::*;
+use json::json;
+use serde::Deserialize;
+use tide::{Request, Response};
+
+use crate::db::models::{CrateRegistration, CrateUserPermission, Permission};
+use crate::db::require_permission;
+use crate::db::schema::*;
+use crate::error::{AlexError, Error};
+use crate::utils;
+use crate::State;
+
+/// The owner-grant endpoint's request body.
+///
+/// Deliberately has no `crate_id` field: the crate being granted on is always the one resolved
+/// from the URL (see [`put`]), never something the caller can redirect to another crate.
+#[derive(Debug, Clone, Deserialize)]
+struct OwnerGrant {
+    /// The author being granted permissions.
+    author_id: i64,
+    /// The granted permission bits, stored as their underlying `i32` representation.
+    permissions: i32,
+}
+
+fn lookup_crate(conn: &diesel::SqliteConnection, name: &str) -> Result<CrateRegistration, Error> {
+    crates::table
+        .filter(crates::name.eq(name))
+        .first::<CrateRegistration>(conn)
+        .optional()?
+        .ok_or_else(|| {
+            Error::from(AlexError::CrateNotFound {
+                name: name.to_string(),
+            })
+        })
+}
+
+/// Route: `PUT /api/v1/crates/<name>/owners`.
+///
+/// Grants an author a set of [`Permission`] bits on the crate, after checking that the
+/// authenticated caller holds [`Permission::MANAGE_USERS`] on it.
+pub async fn put(req: Request<State>) -> Result<Response, Error> {
+    let name = req.param::<String>("name").unwrap_or_default();
+
+    let state = req.state().clone();
+    let conn = state.db.get()?;
+    let caller = state.authenticate(&req)?;
+
+    let krate = lookup_crate(&conn, &name)?;
+    require_permission(&conn, &krate, &caller, Permission::MANAGE_USERS)?;
+
+    let grant: OwnerGrant = req.body_json().await?;
+
+    diesel::replace_into(crate_user_permissions::table)
+        .values(&CrateUserPermission {
+            crate_id: krate.id,
+            author_id: grant.author_id,
+            permissions: grant.permissions,
+        })
+        .execute(&conn)?;
+
+    Ok(utils::response::json(&json!({ "ok": true })))
+}
+
+/// Route: `DELETE /api/v1/crates/<name>/owners/<author_id>`.
+///
+/// Revokes an author's permission bits on the crate, after checking that the authenticated
+/// caller holds [`Permission::MANAGE_USERS`] on it.
+pub async fn delete(req: Request<State>) -> Result<Response, Error> {
+    let name = req.param::<String>("name").unwrap_or_default();
+    let author_id: i64 = req
+        .param::<String>("author_id")
+        .unwrap_or_default()
+        .parse()
+        .map_err(|_| {
+            Error::from(AlexError::MissingQueryParams {
+                missing_params: &["author_id"],
+            })
+        })?;
+
+    let state = req.state().clone();
+    let conn = state.db.get()?;
+    let caller = state.authenticate(&req)?;
+
+    let krate = lookup_crate(&conn, &name)?;
+    require_permission(&conn, &krate, &caller, Permission::MANAGE_USERS)?;
+
+    diesel::delete(
+        crate_user_permissions::table
+            .filter(crate_user_permissions::crate_id.eq(krate.id))
+            .filter(crate_user_permissions::author_id.eq(author_id)),
+    )
+    .execute(&conn)?;
+
+    Ok(utils::response::json(&json!({ "ok": true })))
+}
@@ -0,0 +1,80 @@
+use json::json;
+use serde::Deserialize;
+use tide::{Request, Response};
+
+use crate::db::models::Permission;
+use crate::db::{find_organization, get_or_create_crate, require_permission, update_latest_metadata};
+use crate::error::Error;
+use crate::index::{CrateVersion, Indexer};
+use crate::utils;
+use crate::State;
+
+/// The publish endpoint's request body: the index record for the version being published,
+/// alongside the description/README pulled from the uploaded `.crate` tarball, which get
+/// persisted onto the crate's row (see [`update_latest_metadata`]) rather than the index.
+#[derive(Debug, Clone, Deserialize)]
+struct PublishPayload {
+    /// The version's description, overwriting the crate's previous one.
+    description: Option<String>,
+    /// The version's README, overwriting the crate's previous one.
+    readme: Option<String>,
+    /// The index record itself.
+    #[serde(flatten)]
+    record: CrateVersion,
+}
+
+/// Splits a published crate's name into an optional `org/` namespace prefix and the bare crate
+/// name, following the chartered-style namespacing used by [`get_or_create_crate`].
+fn split_namespace(name: &str) -> (Option<&str>, &str) {
+    match name.find('/') {
+        Some(index) => (Some(&name[..index]), &name[index + 1..]),
+        None => (None, name),
+    }
+}
+
+/// Route: `PUT /api/v1/crates/new`.
+///
+/// Publishes a new version of a crate, auto-creating it first (see [`get_or_create_crate`]) when
+/// it doesn't exist yet. Either way, the authenticated author is checked for
+/// [`Permission::PUBLISH_VERSION`] before the version is added to the index: `get_or_create_crate`
+/// itself requires it (alongside `CREATE_CRATE`) to create the crate and add its first version,
+/// and this handler requires it separately for every subsequent version of an existing crate. The
+/// crate's latest description/README are overwritten with this version's on every publish.
+pub async fn put(mut req: Request<State>) -> Result<Response, Error> {
+    let state = req.state().clone();
+    let conn = state.db.get()?;
+    let author = state.authenticate(&req)?;
+
+    let payload: PublishPayload = req.body_json().await?;
+    let record = payload.record;
+
+    let (org_name, crate_name) = split_namespace(&record.name);
+    let org = org_name.map(|org_name| find_organization(&conn, org_name)).transpose()?;
+
+    let (krate, created) = get_or_create_crate(
+        &conn,
+        &state.index,
+        &author,
+        org.as_ref(),
+        crate_name,
+        record.clone(),
+    )?;
+
+    if !created {
+        require_permission(&conn, &krate, &author, Permission::PUBLISH_VERSION)?;
+        state.index.add_record(record.clone())?;
+    }
+
+    update_latest_metadata(
+        &conn,
+        &krate,
+        payload.description.as_deref(),
+        payload.readme.as_deref(),
+    )?;
+
+    state
+        .index
+        .commit_and_push(&format!("Publishing crate `{} v{}`", record.name, record.vers))?;
+
+    Ok(utils::response::json(&json!({ "ok": true })))
+}
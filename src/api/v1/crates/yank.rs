@@ -0,0 +1,59 @@
+use diesel::prelude::*;
+use json::json;
+use semver::Version;
+use tide::{Request, Response};
+
+use crate::db::models::{CrateRegistration, Permission};
+use crate::db::require_permission;
+use crate::db::schema::*;
+use crate::error::{AlexError, Error};
+use crate::index::Indexer;
+use crate::utils;
+use crate::State;
+
+async fn set_yanked(req: Request<State>, yanked: bool) -> Result<Response, Error> {
+    let name = req.param::<String>("name").unwrap_or_default();
+    let version: Version = req
+        .param::<String>("version")
+        .unwrap_or_default()
+        .parse()?;
+
+    let state = req.state().clone();
+    let conn = state.db.get()?;
+    let author = state.authenticate(&req)?;
+
+    let krate = crates::table
+        .filter(crates::name.eq(name.as_str()))
+        .first::<CrateRegistration>(&conn)
+        .optional()?
+        .ok_or_else(|| AlexError::CrateNotFound { name: name.clone() })?;
+
+    require_permission(&conn, &krate, &author, Permission::YANK_VERSION)?;
+
+    if yanked {
+        state.index.yank_record(&name, version)?;
+    } else {
+        state.index.unyank_record(&name, version)?;
+    }
+    state
+        .index
+        .commit_and_push(&format!("Yanking/unyanking crate `{}`", name))?;
+
+    Ok(utils::response::json(&json!({ "ok": true })))
+}
+
+/// Route: `DELETE /api/v1/crates/<name>/<version>/yank`.
+///
+/// Yanks a published version, after checking that the authenticated author holds
+/// [`Permission::YANK_VERSION`] on the crate.
+pub async fn delete(req: Request<State>) -> Result<Response, Error> {
+    set_yanked(req, true).await
+}
+
+/// Route: `PUT /api/v1/crates/<name>/<version>/unyank`.
+///
+/// Un-yanks a previously yanked version, after checking that the authenticated author holds
+/// [`Permission::YANK_VERSION`] on the crate.
+pub async fn put(req: Request<State>) -> Result<Response, Error> {
+    set_yanked(req, false).await
+}
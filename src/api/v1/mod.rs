@@ -0,0 +1,2 @@
+/// Endpoints operating on crates (publishing, yanking, owner management, info).
+pub mod crates;
@@ -0,0 +1,629 @@
+pub mod models;
+pub mod schema;
+
+use diesel::prelude::*;
+use diesel::result::{DatabaseErrorKind, Error as SQLError};
+use diesel::sqlite::SqliteConnection;
+use rocket_contrib::databases::database;
+
+use crate::db::models::{Author, CrateRegistration, NewCrateRegistration, Organization, Permission};
+use crate::db::schema::{crate_user_permissions, crates, organization_members, organizations};
+use crate::error::{AlexError, Error};
+use crate::index::{CrateVersion, Indexer};
+
+/// The Diesel connection pool used throughout the registry.
+#[database("diesel")]
+pub struct DbConn(pub SqliteConnection);
+
+/// Looks up the permission bits an author holds as a member of an organization.
+///
+/// Authors that are not a member of the organization are granted no permissions at all; callers
+/// that need to distinguish "not a member" from "member with no bits" should check membership
+/// separately (see [`AlexError::NotOrganizationMember`]).
+pub fn organization_permissions(
+    conn: &SqliteConnection,
+    org_id: i64,
+    author_id: i64,
+) -> Result<Permission, Error> {
+    let permissions = organization_members::table
+        .select(organization_members::permissions)
+        .filter(organization_members::org_id.eq(org_id))
+        .filter(organization_members::author_id.eq(author_id))
+        .first::<i32>(conn)
+        .optional()?;
+
+    Ok(permissions
+        .map(Permission::from_bits_truncate)
+        .unwrap_or_else(Permission::empty))
+}
+
+/// Looks up an [`Organization`] by name, returning [`AlexError::OrganizationNotFound`] if there
+/// is none by that name.
+pub fn find_organization(conn: &SqliteConnection, name: &str) -> Result<Organization, Error> {
+    organizations::table
+        .filter(organizations::name.eq(name))
+        .first::<Organization>(conn)
+        .optional()?
+        .ok_or_else(|| {
+            Error::from(AlexError::OrganizationNotFound {
+                name: name.to_string(),
+            })
+        })
+}
+
+/// Looks up the permission bits `author_id` holds as a member of `org`, returning
+/// [`AlexError::NotOrganizationMember`] if they aren't a member at all.
+///
+/// This is distinct from [`organization_permissions`]: it is used by flows (like crate creation)
+/// that need to assert membership itself, as opposed to merely defaulting to an empty permission
+/// set for non-members.
+pub fn require_organization_member(
+    conn: &SqliteConnection,
+    org: &Organization,
+    author_id: i64,
+) -> Result<Permission, Error> {
+    let permissions = organization_members::table
+        .select(organization_members::permissions)
+        .filter(organization_members::org_id.eq(org.id))
+        .filter(organization_members::author_id.eq(author_id))
+        .first::<i32>(conn)
+        .optional()?;
+
+    permissions.map(Permission::from_bits_truncate).ok_or_else(|| {
+        Error::from(AlexError::NotOrganizationMember {
+            org: org.name.clone(),
+        })
+    })
+}
+
+/// Looks up the permission bits an author holds over a given crate.
+///
+/// A crate-specific [`CrateUserPermission`](models::CrateUserPermission) grant, if present, is
+/// authoritative. Otherwise, if the crate is namespaced under an [`Organization`](models::Organization),
+/// the author's org-level bits (see [`organization_permissions`]) are used as the default. An
+/// author with neither is granted no permissions at all.
+pub fn crate_permissions(
+    conn: &SqliteConnection,
+    krate: &CrateRegistration,
+    author_id: i64,
+) -> Result<Permission, Error> {
+    let crate_specific = crate_user_permissions::table
+        .select(crate_user_permissions::permissions)
+        .filter(crate_user_permissions::crate_id.eq(krate.id))
+        .filter(crate_user_permissions::author_id.eq(author_id))
+        .first::<i32>(conn)
+        .optional()?;
+
+    match crate_specific {
+        Some(bits) => Ok(Permission::from_bits_truncate(bits)),
+        None => match krate.org_id {
+            Some(org_id) => organization_permissions(conn, org_id, author_id),
+            None => Ok(Permission::empty()),
+        },
+    }
+}
+
+/// Checks that `author` holds every bit set in `required` over the given crate.
+///
+/// This is the single choke point that the publish, yank/unyank and owner-management flows call
+/// into before delegating to [`crate::index::Indexer`], replacing the previous implicit
+/// all-or-nothing ownership check. When the author holds none of the crate's permission bits at
+/// all, this returns the more specific [`AlexError::CrateNotOwned`]; when they hold some bits but
+/// not the ones `required`, it returns [`AlexError::InsufficientPrivilege`].
+pub fn require_permission(
+    conn: &SqliteConnection,
+    krate: &CrateRegistration,
+    author: &Author,
+    required: Permission,
+) -> Result<(), Error> {
+    let granted = crate_permissions(conn, krate, author.id)?;
+
+    if granted.contains(required) {
+        Ok(())
+    } else if granted.is_empty() {
+        Err(Error::from(AlexError::CrateNotOwned {
+            name: krate.name.clone(),
+            author: author.clone(),
+        }))
+    } else {
+        Err(Error::from(AlexError::InsufficientPrivilege {
+            name: author.name.clone(),
+            required,
+        }))
+    }
+}
+
+/// Fetches the [`CrateRegistration`] for `name`, auto-creating it (and its initial index record)
+/// when it doesn't exist yet and `author` is a member of `org` with both
+/// [`Permission::CREATE_CRATE`] and [`Permission::PUBLISH_VERSION`] — the latter so that creating
+/// a crate can't be used to push its first version without ever being checked for publish rights.
+///
+/// This is called from the publish flow instead of requiring an out-of-band crate registration
+/// step before the first `cargo publish`. When no `org` is given (ie. the crate would not be
+/// namespaced under an organization), creation is always forbidden, since `CREATE_CRATE` is only
+/// ever granted through organization membership.
+///
+/// The membership check only runs when the crate doesn't already exist: an author holding a
+/// crate-specific [`CrateUserPermission`](models::CrateUserPermission) grant (which
+/// [`crate_permissions`] treats as authoritative, independent of org membership) must still be
+/// able to publish to an existing org-namespaced crate without also being a member of its org.
+///
+/// Returns the crate alongside whether it was just created by this call, so that callers (eg.
+/// the publish handler) know whether `initial_record` already accounts for the version being
+/// published, or whether they still need to add it (and check narrower permissions) themselves.
+pub fn get_or_create_crate(
+    conn: &SqliteConnection,
+    indexer: &dyn Indexer,
+    author: &Author,
+    org: Option<&Organization>,
+    name: &str,
+    initial_record: CrateVersion,
+) -> Result<(CrateRegistration, bool), Error> {
+    let org_id = org.map(|org| org.id);
+
+    // The existence check, the creation-permission check and the insert all happen inside a
+    // single transaction, and a unique-constraint violation on `crates.name` (ie. another
+    // concurrent first-publish of the same name racing us between the check and the insert) is
+    // treated the same as having found the row in the first place, instead of surfacing as a raw
+    // `SQLError`.
+    let (krate, created) = conn.transaction::<_, Error, _>(|| {
+        if let Some(krate) = crates::table
+            .filter(crates::name.eq(name))
+            .first::<CrateRegistration>(conn)
+            .optional()?
+        {
+            return Ok((krate, false));
+        }
+
+        let create_permission = match org {
+            Some(org) => require_organization_member(conn, org, author.id)?,
+            None => Permission::empty(),
+        };
+        let required = Permission::CREATE_CRATE | Permission::PUBLISH_VERSION;
+        if !create_permission.contains(required) {
+            return Err(Error::from(AlexError::CrateCreationForbidden {
+                name: name.to_string(),
+            }));
+        }
+
+        let new_crate = NewCrateRegistration {
+            name,
+            description: None,
+            documentation: None,
+            repository: None,
+            org_id,
+        };
+
+        let inserted = diesel::insert_into(crates::table)
+            .values(&new_crate)
+            .execute(conn);
+
+        match inserted {
+            Ok(_) => Ok((
+                crates::table
+                    .filter(crates::name.eq(name))
+                    .first::<CrateRegistration>(conn)?,
+                true,
+            )),
+            Err(SQLError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => Ok((
+                crates::table
+                    .filter(crates::name.eq(name))
+                    .first::<CrateRegistration>(conn)?,
+                false,
+            )),
+            Err(err) => Err(Error::from(err)),
+        }
+    })?;
+
+    if created {
+        indexer.add_record(initial_record)?;
+    }
+
+    Ok((krate, created))
+}
+
+/// Overwrites the crate's latest README and description with those of a newly published version,
+/// and bumps its `updated_at` timestamp.
+///
+/// Following docs.rs/chartered's pattern of tracking a crate's latest release metadata for
+/// display, this is called on every publish (instead of keeping per-version copies), so the
+/// stats page and future crate pages can render a description without re-reading the `.crate`
+/// tarball.
+pub fn update_latest_metadata(
+    conn: &SqliteConnection,
+    krate: &CrateRegistration,
+    description: Option<&str>,
+    readme: Option<&str>,
+) -> Result<(), Error> {
+    diesel::update(crates::table.filter(crates::id.eq(krate.id)))
+        .set((
+            crates::description.eq(description),
+            crates::readme.eq(readme),
+            crates::updated_at.eq(chrono::Utc::now().naive_utc()),
+        ))
+        .execute(conn)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    use diesel::sql_query;
+    use semver::{Version, VersionReq};
+
+    use super::*;
+    use crate::db::models::{CrateUserPermission, OrganizationMember};
+
+    fn setup() -> SqliteConnection {
+        let conn = SqliteConnection::establish(":memory:").unwrap();
+        sql_query(
+            "CREATE TABLE crates (
+                id INTEGER PRIMARY KEY,
+                name TEXT NOT NULL UNIQUE,
+                description TEXT,
+                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                downloads BIGINT NOT NULL DEFAULT 0,
+                documentation TEXT,
+                repository TEXT,
+                org_id BIGINT,
+                readme TEXT
+            )",
+        )
+        .execute(&conn)
+        .unwrap();
+        sql_query("CREATE TABLE organizations (id INTEGER PRIMARY KEY, name TEXT NOT NULL UNIQUE)")
+            .execute(&conn)
+            .unwrap();
+        sql_query(
+            "CREATE TABLE organization_members (
+                org_id BIGINT NOT NULL,
+                author_id BIGINT NOT NULL,
+                permissions INTEGER NOT NULL,
+                PRIMARY KEY (org_id, author_id)
+            )",
+        )
+        .execute(&conn)
+        .unwrap();
+        sql_query(
+            "CREATE TABLE crate_user_permissions (
+                crate_id BIGINT NOT NULL,
+                author_id BIGINT NOT NULL,
+                permissions INTEGER NOT NULL,
+                PRIMARY KEY (crate_id, author_id)
+            )",
+        )
+        .execute(&conn)
+        .unwrap();
+        conn
+    }
+
+    fn author(id: i64) -> Author {
+        Author {
+            id,
+            email: None,
+            name: format!("author-{}", id),
+            passwd: String::new(),
+        }
+    }
+
+    fn insert_crate(conn: &SqliteConnection, name: &str, org_id: Option<i64>) -> CrateRegistration {
+        diesel::insert_into(crates::table)
+            .values(&NewCrateRegistration {
+                name,
+                description: None,
+                documentation: None,
+                repository: None,
+                org_id,
+            })
+            .execute(conn)
+            .unwrap();
+        crates::table
+            .filter(crates::name.eq(name))
+            .first(conn)
+            .unwrap()
+    }
+
+    fn insert_organization(conn: &SqliteConnection, name: &str) -> Organization {
+        diesel::insert_into(organizations::table)
+            .values(organizations::name.eq(name))
+            .execute(conn)
+            .unwrap();
+        organizations::table
+            .filter(organizations::name.eq(name))
+            .first(conn)
+            .unwrap()
+    }
+
+    fn sample_record(name: &str) -> CrateVersion {
+        CrateVersion {
+            name: name.to_string(),
+            vers: Version::new(1, 0, 0),
+            deps: Vec::new(),
+            features: HashMap::new(),
+            yanked: None,
+        }
+    }
+
+    /// A no-op [`Indexer`] that just records the records it was asked to add.
+    struct RecordingIndexer {
+        added: RefCell<Vec<CrateVersion>>,
+    }
+
+    impl RecordingIndexer {
+        fn new() -> Self {
+            RecordingIndexer {
+                added: RefCell::new(Vec::new()),
+            }
+        }
+    }
+
+    impl Indexer for RecordingIndexer {
+        fn url(&self) -> Result<String, Error> {
+            Ok(String::new())
+        }
+
+        fn refresh(&self) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn all_records(&self, _name: &str) -> Result<Vec<CrateVersion>, Error> {
+            Ok(Vec::new())
+        }
+
+        fn latest_record(&self, name: &str) -> Result<CrateVersion, Error> {
+            Err(Error::from(AlexError::CrateNotFound {
+                name: name.to_string(),
+            }))
+        }
+
+        fn match_record(&self, name: &str, _req: VersionReq) -> Result<CrateVersion, Error> {
+            Err(Error::from(AlexError::CrateNotFound {
+                name: name.to_string(),
+            }))
+        }
+
+        fn commit_and_push(&self, _msg: &str) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn add_record(&self, record: CrateVersion) -> Result<(), Error> {
+            self.added.borrow_mut().push(record);
+            Ok(())
+        }
+
+        fn alter_record<F>(&self, _name: &str, _version: Version, _func: F) -> Result<(), Error>
+        where
+            F: FnOnce(&mut CrateVersion),
+        {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn require_permission_reports_crate_not_owned_when_no_bits_are_granted() {
+        let conn = setup();
+        let krate = insert_crate(&conn, "demo", None);
+        let author = author(1);
+
+        let err = require_permission(&conn, &krate, &author, Permission::PUBLISH_VERSION)
+            .expect_err("author holds no permissions at all");
+        assert!(matches!(
+            err,
+            Error::AlexError(AlexError::CrateNotOwned { .. })
+        ));
+    }
+
+    #[test]
+    fn require_permission_reports_insufficient_privilege_when_some_bits_are_granted() {
+        let conn = setup();
+        let krate = insert_crate(&conn, "demo", None);
+        let author = author(1);
+
+        diesel::insert_into(crate_user_permissions::table)
+            .values(&CrateUserPermission {
+                crate_id: krate.id,
+                author_id: author.id,
+                permissions: Permission::YANK_VERSION.bits(),
+            })
+            .execute(&conn)
+            .unwrap();
+
+        let err = require_permission(&conn, &krate, &author, Permission::PUBLISH_VERSION)
+            .expect_err("author only holds YANK_VERSION");
+        assert!(matches!(
+            err,
+            Error::AlexError(AlexError::InsufficientPrivilege { .. })
+        ));
+    }
+
+    #[test]
+    fn require_permission_succeeds_when_the_required_bit_is_granted() {
+        let conn = setup();
+        let krate = insert_crate(&conn, "demo", None);
+        let author = author(1);
+
+        diesel::insert_into(crate_user_permissions::table)
+            .values(&CrateUserPermission {
+                crate_id: krate.id,
+                author_id: author.id,
+                permissions: Permission::PUBLISH_VERSION.bits(),
+            })
+            .execute(&conn)
+            .unwrap();
+
+        require_permission(&conn, &krate, &author, Permission::PUBLISH_VERSION).unwrap();
+    }
+
+    #[test]
+    fn crate_permissions_fall_back_to_the_organization_when_unset() {
+        let conn = setup();
+        let org = insert_organization(&conn, "acme");
+        let krate = insert_crate(&conn, "demo", Some(org.id));
+        let author = author(1);
+
+        diesel::insert_into(organization_members::table)
+            .values(&OrganizationMember {
+                org_id: org.id,
+                author_id: author.id,
+                permissions: Permission::PUBLISH_VERSION.bits(),
+            })
+            .execute(&conn)
+            .unwrap();
+
+        require_permission(&conn, &krate, &author, Permission::PUBLISH_VERSION).unwrap();
+    }
+
+    #[test]
+    fn find_organization_reports_organization_not_found() {
+        let conn = setup();
+        let err = find_organization(&conn, "nonexistent").expect_err("no such organization");
+        assert!(matches!(
+            err,
+            Error::AlexError(AlexError::OrganizationNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn require_organization_member_reports_not_organization_member() {
+        let conn = setup();
+        let org = insert_organization(&conn, "acme");
+
+        let err = require_organization_member(&conn, &org, 1).expect_err("author isn't a member");
+        assert!(matches!(
+            err,
+            Error::AlexError(AlexError::NotOrganizationMember { .. })
+        ));
+    }
+
+    #[test]
+    fn get_or_create_crate_returns_the_existing_crate_without_creating_it() {
+        let conn = setup();
+        let existing = insert_crate(&conn, "demo", None);
+        let author = author(1);
+        let indexer = RecordingIndexer::new();
+
+        let (krate, created) = get_or_create_crate(
+            &conn,
+            &indexer,
+            &author,
+            None,
+            "demo",
+            sample_record("demo"),
+        )
+        .unwrap();
+
+        assert_eq!(krate.id, existing.id);
+        assert!(!created);
+        assert!(indexer.added.borrow().is_empty());
+    }
+
+    #[test]
+    fn get_or_create_crate_creates_the_crate_when_the_author_can_create() {
+        let conn = setup();
+        let org = insert_organization(&conn, "acme");
+        let author = author(1);
+        diesel::insert_into(organization_members::table)
+            .values(&OrganizationMember {
+                org_id: org.id,
+                author_id: author.id,
+                permissions: (Permission::CREATE_CRATE | Permission::PUBLISH_VERSION).bits(),
+            })
+            .execute(&conn)
+            .unwrap();
+        let indexer = RecordingIndexer::new();
+
+        let (krate, created) = get_or_create_crate(
+            &conn,
+            &indexer,
+            &author,
+            Some(&org),
+            "demo",
+            sample_record("demo"),
+        )
+        .unwrap();
+
+        assert_eq!(krate.name, "demo");
+        assert!(created);
+        assert_eq!(indexer.added.borrow().len(), 1);
+    }
+
+    #[test]
+    fn get_or_create_crate_forbids_creation_without_an_organization() {
+        let conn = setup();
+        let author = author(1);
+        let indexer = RecordingIndexer::new();
+
+        let err = get_or_create_crate(&conn, &indexer, &author, None, "demo", sample_record("demo"))
+            .expect_err("creation outside of an organization is always forbidden");
+        assert!(matches!(
+            err,
+            Error::AlexError(AlexError::CrateCreationForbidden { .. })
+        ));
+    }
+
+    #[test]
+    fn get_or_create_crate_forbids_creation_with_create_crate_but_not_publish_version() {
+        let conn = setup();
+        let org = insert_organization(&conn, "acme");
+        let author = author(1);
+        diesel::insert_into(organization_members::table)
+            .values(&OrganizationMember {
+                org_id: org.id,
+                author_id: author.id,
+                permissions: Permission::CREATE_CRATE.bits(),
+            })
+            .execute(&conn)
+            .unwrap();
+        let indexer = RecordingIndexer::new();
+
+        let err = get_or_create_crate(
+            &conn,
+            &indexer,
+            &author,
+            Some(&org),
+            "demo",
+            sample_record("demo"),
+        )
+        .expect_err("CREATE_CRATE alone must not also grant the right to publish the first version");
+        assert!(matches!(
+            err,
+            Error::AlexError(AlexError::CrateCreationForbidden { .. })
+        ));
+        assert!(indexer.added.borrow().is_empty());
+    }
+
+    #[test]
+    fn get_or_create_crate_skips_the_membership_check_for_an_existing_crate() {
+        let conn = setup();
+        let org = insert_organization(&conn, "acme");
+        let existing = insert_crate(&conn, "demo", Some(org.id));
+        let author = author(1);
+        // `author` holds a crate-specific grant but is not a member of `org` at all.
+        diesel::insert_into(crate_user_permissions::table)
+            .values(&CrateUserPermission {
+                crate_id: existing.id,
+                author_id: author.id,
+                permissions: Permission::PUBLISH_VERSION.bits(),
+            })
+            .execute(&conn)
+            .unwrap();
+        let indexer = RecordingIndexer::new();
+
+        let (krate, created) = get_or_create_crate(
+            &conn,
+            &indexer,
+            &author,
+            Some(&org),
+            "demo",
+            sample_record("demo"),
+        )
+        .expect("a crate-specific grant must work without org membership");
+
+        assert_eq!(krate.id, existing.id);
+        assert!(!created);
+    }
+}
@@ -0,0 +1,157 @@
+use bitflags::bitflags;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::db::schema::*;
+
+/// A registered user of the registry.
+#[derive(Debug, Clone, Identifiable, Queryable, QueryableByName)]
+#[table_name = "authors"]
+pub struct Author {
+    /// The author's unique identifier.
+    pub id: i64,
+    /// The author's email address.
+    pub email: Option<String>,
+    /// The author's display name.
+    pub name: String,
+    /// The author's hashed password.
+    pub passwd: String,
+}
+
+/// A registered crate.
+#[derive(Debug, Clone, Identifiable, Queryable, QueryableByName, Serialize)]
+#[table_name = "crates"]
+pub struct CrateRegistration {
+    /// The crate's unique identifier.
+    pub id: i64,
+    /// The crate's name.
+    pub name: String,
+    /// The crate's description.
+    pub description: Option<String>,
+    /// The date the crate was first registered.
+    pub created_at: NaiveDateTime,
+    /// The date the crate was last updated (ie. a new version was published).
+    pub updated_at: NaiveDateTime,
+    /// The crate's cumulated download count.
+    pub downloads: i64,
+    /// The crate's documentation URL.
+    pub documentation: Option<String>,
+    /// The crate's repository URL.
+    pub repository: Option<String>,
+    /// The [`Organization`] the crate is namespaced under, if any.
+    pub org_id: Option<i64>,
+    /// The README of the most recently published version, overwritten on each publish.
+    pub readme: Option<String>,
+}
+
+/// The fields needed to insert a new [`CrateRegistration`] row.
+///
+/// Used when auto-creating a crate on its first publish (see [`crate::db::get_or_create_crate`]).
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "crates"]
+pub struct NewCrateRegistration<'a> {
+    /// The crate's name.
+    pub name: &'a str,
+    /// The crate's description.
+    pub description: Option<&'a str>,
+    /// The crate's documentation URL.
+    pub documentation: Option<&'a str>,
+    /// The crate's repository URL.
+    pub repository: Option<&'a str>,
+    /// The [`Organization`] the crate is namespaced under, if any.
+    pub org_id: Option<i64>,
+}
+
+/// An ownership relation between an [`Author`] and a [`CrateRegistration`].
+#[derive(Debug, Clone, Identifiable, Queryable, QueryableByName, Insertable)]
+#[table_name = "crate_authors"]
+#[primary_key(crate_id, author_id)]
+pub struct CrateAuthor {
+    /// The owned crate's identifier.
+    pub crate_id: i64,
+    /// The owning author's identifier.
+    pub author_id: i64,
+}
+
+bitflags! {
+    /// The set of actions an [`Author`] is allowed to perform on a given crate.
+    ///
+    /// This mirrors the model used by [chartered](https://github.com/chartered/chartered), where
+    /// every user holds a bitwise permission set scoped to a single crate, instead of the
+    /// all-or-nothing "is this author an owner of the crate" check used previously.
+    #[derive(Serialize, Deserialize)]
+    pub struct Permission: i32 {
+        /// Grants visibility into the crate (eg. for crates hosted on a private registry).
+        const VISIBLE = 0b0000_0001;
+        /// Grants the right to publish new versions of the crate.
+        const PUBLISH_VERSION = 0b0000_0010;
+        /// Grants the right to yank/unyank versions of the crate.
+        const YANK_VERSION = 0b0000_0100;
+        /// Grants the right to manage the crate's owners and their permissions.
+        const MANAGE_USERS = 0b0000_1000;
+        /// Grants the right to create a crate under an organization's namespace.
+        const CREATE_CRATE = 0b0001_0000;
+    }
+}
+
+impl Default for Permission {
+    fn default() -> Self {
+        Permission::empty()
+    }
+}
+
+/// The permission bits an [`Author`] holds over a specific [`CrateRegistration`].
+#[derive(Debug, Clone, Identifiable, Queryable, QueryableByName, Insertable)]
+#[table_name = "crate_user_permissions"]
+#[primary_key(crate_id, author_id)]
+pub struct CrateUserPermission {
+    /// The crate the permissions apply to.
+    pub crate_id: i64,
+    /// The author the permissions are granted to.
+    pub author_id: i64,
+    /// The granted permission bits, stored as their underlying `i32` representation.
+    pub permissions: i32,
+}
+
+impl CrateUserPermission {
+    /// Decodes the stored bits into a [`Permission`] set.
+    pub fn permission(&self) -> Permission {
+        Permission::from_bits_truncate(self.permissions)
+    }
+}
+
+/// A named group of crates, inspired by [chartered](https://github.com/chartered/chartered)'s
+/// organisations feature.
+///
+/// Crates namespaced under an organization (see [`CrateRegistration::org_id`]) inherit its
+/// members' permission bits by default, unless overridden by a crate-specific
+/// [`CrateUserPermission`] grant.
+#[derive(Debug, Clone, Identifiable, Queryable, QueryableByName, Serialize)]
+#[table_name = "organizations"]
+pub struct Organization {
+    /// The organization's unique identifier.
+    pub id: i64,
+    /// The organization's name.
+    pub name: String,
+}
+
+/// The permission bits an [`Author`] holds as a member of an [`Organization`].
+#[derive(Debug, Clone, Identifiable, Queryable, QueryableByName, Insertable)]
+#[table_name = "organization_members"]
+#[primary_key(org_id, author_id)]
+pub struct OrganizationMember {
+    /// The organization the membership belongs to.
+    pub org_id: i64,
+    /// The member author's identifier.
+    pub author_id: i64,
+    /// The granted permission bits, stored as their underlying `i32` representation.
+    pub permissions: i32,
+}
+
+impl OrganizationMember {
+    /// Decodes the stored bits into a [`Permission`] set.
+    pub fn permission(&self) -> Permission {
+        Permission::from_bits_truncate(self.permissions)
+    }
+}
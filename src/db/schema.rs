@@ -0,0 +1,70 @@
+table! {
+    authors (id) {
+        id -> BigInt,
+        email -> Nullable<Text>,
+        name -> Text,
+        passwd -> Text,
+    }
+}
+
+table! {
+    crates (id) {
+        id -> BigInt,
+        name -> Text,
+        description -> Nullable<Text>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        downloads -> BigInt,
+        documentation -> Nullable<Text>,
+        repository -> Nullable<Text>,
+        org_id -> Nullable<BigInt>,
+        readme -> Nullable<Text>,
+    }
+}
+
+table! {
+    organizations (id) {
+        id -> BigInt,
+        name -> Text,
+    }
+}
+
+table! {
+    organization_members (org_id, author_id) {
+        org_id -> BigInt,
+        author_id -> BigInt,
+        permissions -> Integer,
+    }
+}
+
+table! {
+    crate_authors (crate_id, author_id) {
+        crate_id -> BigInt,
+        author_id -> BigInt,
+    }
+}
+
+table! {
+    crate_user_permissions (crate_id, author_id) {
+        crate_id -> BigInt,
+        author_id -> BigInt,
+        permissions -> Integer,
+    }
+}
+
+joinable!(crate_authors -> crates (crate_id));
+joinable!(crate_authors -> authors (author_id));
+joinable!(crate_user_permissions -> crates (crate_id));
+joinable!(crate_user_permissions -> authors (author_id));
+joinable!(crates -> organizations (org_id));
+joinable!(organization_members -> organizations (org_id));
+joinable!(organization_members -> authors (author_id));
+
+allow_tables_to_appear_in_same_query!(
+    authors,
+    crates,
+    crate_authors,
+    crate_user_permissions,
+    organizations,
+    organization_members,
+);
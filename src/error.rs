@@ -12,7 +12,7 @@ use toml::de::Error as TOMLError;
 use tide::response::IntoResponse;
 use tide::Response;
 
-use crate::db::models::Author;
+use crate::db::models::{Author, Permission};
 use crate::utils;
 use http::StatusCode;
 
@@ -39,6 +39,9 @@ pub enum Error {
     /// Hexadecimal decoding errors (odd length, etc...).
     #[error("Hex error: {0}")]
     HexError(#[source] HexError),
+    /// Git2 error (invalid repository state, authentication failure, etc...).
+    #[error("Git2 error: {0}")]
+    Git2Error(#[source] git2::Error),
     /// Alexandrie's custom errors (crate not found, invalid token, etc...).
     #[error("Alexandrie error: {0}")]
     AlexError(#[source] AlexError),
@@ -54,6 +57,10 @@ pub enum AlexError {
         name: String,
     },
     /// The crate is not owned by the user.
+    ///
+    /// This is now one specific case of the broader [`AlexError::InsufficientPrivilege`] check
+    /// (ie. the author holds none of the crate's permission bits at all), kept around for the
+    /// more specific message it gives in that situation.
     #[error("you are not an owner of '{name}'")]
     CrateNotOwned {
         /// The involved crate's name.
@@ -61,6 +68,14 @@ pub enum AlexError {
         /// The involved author.
         author: Author,
     },
+    /// The author does not hold the required permission bits over the crate.
+    #[error("'{name}' does not have the required permissions ({required:?})")]
+    InsufficientPrivilege {
+        /// The involved author's name.
+        name: String,
+        /// The permission bits that were required but not granted.
+        required: Permission,
+    },
     /// The published crate version is lower than the current hosted version.
     #[error("the published version is too low (hosted version is {hosted}, and thus {published} <= {hosted})")]
     VersionTooLow {
@@ -80,17 +95,39 @@ pub enum AlexError {
         /// The list of missing query parameters.
         missing_params: &'static [&'static str],
     },
+    /// The requested organization cannot be found.
+    #[error("no organization named '{name}' found")]
+    OrganizationNotFound {
+        /// The requested organization's name.
+        name: String,
+    },
+    /// The author is not a member of the organization.
+    #[error("you are not a member of organization '{org}'")]
+    NotOrganizationMember {
+        /// The involved organization's name.
+        org: String,
+    },
+    /// The crate does not exist yet, and the author is not allowed to create it.
+    #[error("you are not allowed to create a new crate named '{name}'")]
+    CrateCreationForbidden {
+        /// The crate name that was about to be created.
+        name: String,
+    },
 }
 
 impl AlexError {
     /// Function to map `AlexError` to an appropriate HTTP error code
     pub fn get_http_status_code(&self) -> StatusCode {
         match self {
-            AlexError::CrateNotFound { .. } => http::StatusCode::BAD_REQUEST,
-            AlexError::CrateNotOwned { .. } => http::StatusCode::BAD_REQUEST,
+            AlexError::CrateNotFound { .. } => http::StatusCode::NOT_FOUND,
+            AlexError::CrateNotOwned { .. } => http::StatusCode::FORBIDDEN,
+            AlexError::InsufficientPrivilege { .. } => http::StatusCode::FORBIDDEN,
             AlexError::VersionTooLow { .. } => http::StatusCode::BAD_REQUEST,
             AlexError::InvalidToken => http::StatusCode::UNAUTHORIZED,
             AlexError::MissingQueryParams { .. } => http::StatusCode::BAD_REQUEST,
+            AlexError::OrganizationNotFound { .. } => http::StatusCode::NOT_FOUND,
+            AlexError::NotOrganizationMember { .. } => http::StatusCode::FORBIDDEN,
+            AlexError::CrateCreationForbidden { .. } => http::StatusCode::FORBIDDEN,
         }
     }
 }
@@ -104,6 +141,7 @@ impl IntoResponse for Error {
             Error::SQLError(_) => "internal server error".to_string(),
             Error::SemverError(_) => "internal server error".to_string(),
             Error::HexError(_) => "internal server error".to_string(),
+            Error::Git2Error(_) => "internal server error".to_string(),
             Error::AlexError(err) => err.to_string(),
         };
         let status_code = match self {
@@ -152,6 +190,12 @@ impl From<HexError> for Error {
     }
 }
 
+impl From<git2::Error> for Error {
+    fn from(err: git2::Error) -> Error {
+        Error::Git2Error(err)
+    }
+}
+
 impl From<AlexError> for Error {
     fn from(err: AlexError) -> Error {
         Error::AlexError(err)
@@ -213,6 +257,17 @@ impl TryInto<SemverError> for Error {
     }
 }
 
+impl TryInto<git2::Error> for Error {
+    type Error = ();
+
+    fn try_into(self) -> Result<git2::Error, Self::Error> {
+        match self {
+            Error::Git2Error(err) => Ok(err),
+            _ => Err(()),
+        }
+    }
+}
+
 impl TryInto<AlexError> for Error {
     type Error = ();
 
@@ -4,7 +4,6 @@ use json::json;
 use rocket::State;
 use rocket_contrib::templates::Template;
 
-use crate::db::models::CrateRegistration;
 use crate::db::schema::*;
 use crate::db::DbConn;
 use crate::error::Error;
@@ -21,23 +20,29 @@ pub(crate) fn route(config: State<Config>, conn: DbConn) -> Result<Template, Err
         .first::<Option<BigDecimal>>(&conn.0)?
         .unwrap_or(BigDecimal::from(0));
     let most_downloaded = crates::table
+        .select((crates::name, crates::description, crates::downloads))
         .order_by(crates::downloads.desc())
         .limit(10)
-        .load::<CrateRegistration>(&conn.0)?;
+        .load::<(String, Option<String>, i64)>(&conn.0)?;
     let last_updated = crates::table
-        .select((crates::name, crates::updated_at))
+        .select((crates::name, crates::description, crates::updated_at))
         .order_by(crates::updated_at.desc())
         .limit(10)
-        .load::<(String, chrono::NaiveDateTime)>(&conn.0)?;
+        .load::<(String, Option<String>, chrono::NaiveDateTime)>(&conn.0)?;
     Ok(Template::render(
         "index",
         json!({
             "instance": config.inner(),
             "total_downloads": total_downloads,
             "crate_count": crate_count,
-            "most_downloaded": most_downloaded,
-            "last_updated": last_updated.into_iter().map(|(name, date)| json!({
+            "most_downloaded": most_downloaded.into_iter().map(|(name, description, downloads)| json!({
                 "name": name,
+                "description": description,
+                "downloads": downloads,
+            })).collect::<Vec<_>>(),
+            "last_updated": last_updated.into_iter().map(|(name, description, date)| json!({
+                "name": name,
+                "description": description,
                 "updated_at": helpers::humanize_datetime(date),
             })).collect::<Vec<_>>(),
         }),
@@ -0,0 +1,326 @@
+use std::fs;
+use std::path::PathBuf;
+
+use git2::{Cred, FetchOptions, PushOptions, RemoteCallbacks, Repository, Signature};
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{AlexError, Error};
+use crate::index::{CrateVersion, Indexer};
+
+/// The authentication method used when contacting the upstream index remote.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "auth")]
+pub enum Git2Credentials {
+    /// No authentication (eg. a local remote, or one already authorized through other means).
+    #[serde(rename = "none")]
+    None,
+    /// HTTPS authentication through a username/password (or access token) pair.
+    #[serde(rename = "https")]
+    Https {
+        /// The username to authenticate with.
+        username: String,
+        /// The password (or access token) to authenticate with.
+        password: String,
+    },
+    /// SSH authentication through a private key file.
+    #[serde(rename = "ssh")]
+    Ssh {
+        /// The username to authenticate with (usually `git`).
+        username: String,
+        /// The path to the SSH private key to authenticate with.
+        key_path: PathBuf,
+        /// The passphrase protecting the private key, if any.
+        passphrase: Option<String>,
+    },
+}
+
+impl Default for Git2Credentials {
+    fn default() -> Self {
+        Git2Credentials::None
+    }
+}
+
+fn default_branch() -> String {
+    String::from("master")
+}
+
+/// Index management through the [`git2`](https://docs.rs/git2) crate's libgit2 bindings.
+///
+/// This avoids depending on a `git` binary being present on `PATH`, and reports every failure
+/// as a proper [`Error`] instead of requiring the parsing of a subprocess' standard error.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Git2Index {
+    /// The path to the local clone of the crate index.
+    pub path: PathBuf,
+    /// The URL of the upstream index repository.
+    pub url: String,
+    /// The branch of the upstream index repository to track.
+    #[serde(default = "default_branch")]
+    pub branch: String,
+    /// The credentials used when fetching from / pushing to the upstream remote.
+    #[serde(default)]
+    pub credentials: Git2Credentials,
+}
+
+impl Git2Index {
+    fn open(&self) -> Result<Repository, Error> {
+        Repository::open(&self.path).map_err(Error::from)
+    }
+
+    fn signature<'a>(&self, repo: &'a Repository) -> Result<Signature<'a>, Error> {
+        repo.signature()
+            .or_else(|_| Signature::now("Alexandrie", "alexandrie@localhost"))
+            .map_err(Error::from)
+    }
+
+    fn fetch_options(&self) -> FetchOptions<'_> {
+        let mut callbacks = RemoteCallbacks::new();
+        callbacks.credentials(move |_url, username_from_url, _allowed_types| {
+            match &self.credentials {
+                Git2Credentials::None => Cred::default(),
+                Git2Credentials::Https { username, password } => {
+                    Cred::userpass_plaintext(username, password)
+                }
+                Git2Credentials::Ssh {
+                    username,
+                    key_path,
+                    passphrase,
+                } => Cred::ssh_key(
+                    username_from_url.unwrap_or(username),
+                    None,
+                    key_path,
+                    passphrase.as_deref(),
+                ),
+            }
+        });
+
+        let mut options = FetchOptions::new();
+        options.remote_callbacks(callbacks);
+        options
+    }
+
+    fn push_options(&self) -> PushOptions<'_> {
+        let mut callbacks = RemoteCallbacks::new();
+        callbacks.credentials(move |_url, username_from_url, _allowed_types| {
+            match &self.credentials {
+                Git2Credentials::None => Cred::default(),
+                Git2Credentials::Https { username, password } => {
+                    Cred::userpass_plaintext(username, password)
+                }
+                Git2Credentials::Ssh {
+                    username,
+                    key_path,
+                    passphrase,
+                } => Cred::ssh_key(
+                    username_from_url.unwrap_or(username),
+                    None,
+                    key_path,
+                    passphrase.as_deref(),
+                ),
+            }
+        });
+
+        let mut options = PushOptions::new();
+        options.remote_callbacks(callbacks);
+        options
+    }
+
+    /// Computes the path (relative to the index's root) at which a given crate's records live.
+    ///
+    /// This follows the same bucketing scheme as the upstream crates.io index: crates with a
+    /// one or two-letter name get their own top-level bucket, three-letter names are nested
+    /// under a bucket named after their own length, and everything else is nested under the
+    /// first two and next two letters of the crate's name.
+    fn record_path(&self, name: &str) -> PathBuf {
+        let lower = name.to_lowercase();
+        let mut path = self.path.clone();
+        match lower.len() {
+            1 => path.push("1"),
+            2 => path.push("2"),
+            3 => {
+                path.push("3");
+                path.push(&lower[..1]);
+            }
+            _ => {
+                path.push(&lower[0..2]);
+                path.push(&lower[2..4]);
+            }
+        }
+        path.push(lower);
+        path
+    }
+
+    fn read_records(&self, name: &str) -> Result<Vec<CrateVersion>, Error> {
+        let path = self.record_path(name);
+        if !path.is_file() {
+            return Ok(Vec::new());
+        }
+        let contents = fs::read_to_string(&path)?;
+        let records = contents
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| Ok(json::from_str::<CrateVersion>(line)?))
+            .collect::<Result<Vec<CrateVersion>, Error>>()?;
+        Ok(records)
+    }
+
+    fn write_records(&self, name: &str, records: &[CrateVersion]) -> Result<(), Error> {
+        let path = self.record_path(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut contents = String::new();
+        for record in records {
+            contents.push_str(&json::to_string(record)?);
+            contents.push('\n');
+        }
+        fs::write(&path, contents)?;
+
+        let repo = self.open()?;
+        let relative = path.strip_prefix(&self.path).unwrap_or(&path);
+        let mut index = repo.index()?;
+        index.add_path(relative)?;
+        index.write()?;
+
+        Ok(())
+    }
+}
+
+impl Indexer for Git2Index {
+    fn url(&self) -> Result<String, Error> {
+        Ok(self.url.clone())
+    }
+
+    fn refresh(&self) -> Result<(), Error> {
+        let repo = self.open()?;
+        let mut remote = repo.find_remote("origin")?;
+        remote.fetch(&[self.branch.as_str()], Some(&mut self.fetch_options()), None)?;
+
+        let reference = repo.find_reference(&format!("refs/remotes/origin/{}", self.branch))?;
+        let target = reference.peel_to_commit()?;
+        repo.reset(target.as_object(), git2::ResetType::Hard, None)?;
+
+        Ok(())
+    }
+
+    fn all_records(&self, name: &str) -> Result<Vec<CrateVersion>, Error> {
+        self.read_records(name)
+    }
+
+    fn latest_record(&self, name: &str) -> Result<CrateVersion, Error> {
+        self.read_records(name)?
+            .into_iter()
+            .max_by(|a, b| a.vers.cmp(&b.vers))
+            .ok_or_else(|| {
+                Error::from(AlexError::CrateNotFound {
+                    name: name.to_string(),
+                })
+            })
+    }
+
+    fn match_record(&self, name: &str, req: VersionReq) -> Result<CrateVersion, Error> {
+        self.read_records(name)?
+            .into_iter()
+            .filter(|record| req.matches(&record.vers))
+            .max_by(|a, b| a.vers.cmp(&b.vers))
+            .ok_or_else(|| {
+                Error::from(AlexError::CrateNotFound {
+                    name: name.to_string(),
+                })
+            })
+    }
+
+    fn commit_and_push(&self, msg: &str) -> Result<(), Error> {
+        let repo = self.open()?;
+        let mut index = repo.index()?;
+        let tree_id = index.write_tree()?;
+        let tree = repo.find_tree(tree_id)?;
+        let signature = self.signature(&repo)?;
+        let parent = repo
+            .head()
+            .and_then(|head| head.peel_to_commit())
+            .ok();
+        let parents = parent.iter().collect::<Vec<_>>();
+
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            msg,
+            &tree,
+            parents.as_slice(),
+        )?;
+
+        let mut remote = repo.find_remote("origin")?;
+        let refspec = format!("refs/heads/{branch}:refs/heads/{branch}", branch = self.branch);
+        remote.push(&[refspec.as_str()], Some(&mut self.push_options()))?;
+
+        Ok(())
+    }
+
+    fn add_record(&self, record: CrateVersion) -> Result<(), Error> {
+        let name = record.name.clone();
+        let mut records = self.read_records(&name)?;
+        records.push(record);
+        self.write_records(&name, &records)
+    }
+
+    fn alter_record<F>(&self, name: &str, version: Version, func: F) -> Result<(), Error>
+    where
+        F: FnOnce(&mut CrateVersion),
+    {
+        let mut records = self.read_records(name)?;
+        let record = records
+            .iter_mut()
+            .find(|record| record.vers == version)
+            .ok_or_else(|| {
+                Error::from(AlexError::CrateNotFound {
+                    name: name.to_string(),
+                })
+            })?;
+        func(record);
+        self.write_records(name, &records)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index() -> Git2Index {
+        Git2Index {
+            path: PathBuf::from("/tmp/index"),
+            url: String::from("https://example.com/index.git"),
+            branch: default_branch(),
+            credentials: Git2Credentials::None,
+        }
+    }
+
+    #[test]
+    fn record_path_buckets_one_and_two_letter_names() {
+        assert_eq!(index().record_path("a"), PathBuf::from("/tmp/index/1/a"));
+        assert_eq!(index().record_path("ab"), PathBuf::from("/tmp/index/2/ab"));
+    }
+
+    #[test]
+    fn record_path_buckets_three_letter_names_under_their_first_letter() {
+        assert_eq!(index().record_path("abc"), PathBuf::from("/tmp/index/3/a/abc"));
+    }
+
+    #[test]
+    fn record_path_buckets_longer_names_under_first_two_and_next_two_letters() {
+        assert_eq!(
+            index().record_path("serde"),
+            PathBuf::from("/tmp/index/se/rd/serde")
+        );
+    }
+
+    #[test]
+    fn record_path_lowercases_the_bucketed_name() {
+        assert_eq!(
+            index().record_path("Serde"),
+            PathBuf::from("/tmp/index/se/rd/serde")
+        );
+    }
+}
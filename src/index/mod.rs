@@ -3,12 +3,15 @@ use serde::{Deserialize, Serialize};
 
 /// Index management through `git` shell command invocations.
 pub mod cli;
+/// Index management through the `git2` crate's libgit2 bindings.
+pub mod git2;
 mod models;
 
 pub use models::*;
 
 use crate::error::Error;
 use crate::index::cli::CommandLineIndex;
+use crate::index::git2::Git2Index;
 
 /// The crate indexing management strategy type.
 ///
@@ -19,8 +22,9 @@ pub enum Index {
     /// Manages the crate index through the invocation of the "git" shell command.
     #[serde(rename = "command-line")]
     CommandLine(CommandLineIndex),
-    // TODO: Add an `Indexer` implementation using `git2`.
-    // Git2(Git2Index),
+    /// Manages the crate index through the `git2` crate's libgit2 bindings.
+    #[serde(rename = "git2")]
+    Git2(Git2Index),
 }
 
 /// The required trait that any crate index management type must implement.
@@ -57,42 +61,49 @@ impl Indexer for Index {
     fn url(&self) -> Result<String, Error> {
         match self {
             Index::CommandLine(idx) => idx.url(),
+            Index::Git2(idx) => idx.url(),
         }
     }
 
     fn refresh(&self) -> Result<(), Error> {
         match self {
             Index::CommandLine(idx) => idx.refresh(),
+            Index::Git2(idx) => idx.refresh(),
         }
     }
 
     fn commit_and_push(&self, msg: &str) -> Result<(), Error> {
         match self {
             Index::CommandLine(idx) => idx.commit_and_push(msg),
+            Index::Git2(idx) => idx.commit_and_push(msg),
         }
     }
 
     fn all_records(&self, name: &str) -> Result<Vec<CrateVersion>, Error> {
         match self {
             Index::CommandLine(idx) => idx.all_records(name),
+            Index::Git2(idx) => idx.all_records(name),
         }
     }
 
     fn latest_record(&self, name: &str) -> Result<CrateVersion, Error> {
         match self {
             Index::CommandLine(idx) => idx.latest_record(name),
+            Index::Git2(idx) => idx.latest_record(name),
         }
     }
 
     fn match_record(&self, name: &str, req: VersionReq) -> Result<CrateVersion, Error> {
         match self {
             Index::CommandLine(idx) => idx.match_record(name, req),
+            Index::Git2(idx) => idx.match_record(name, req),
         }
     }
 
     fn add_record(&self, record: CrateVersion) -> Result<(), Error> {
         match self {
             Index::CommandLine(idx) => idx.add_record(record),
+            Index::Git2(idx) => idx.add_record(record),
         }
     }
 
@@ -102,18 +113,21 @@ impl Indexer for Index {
     {
         match self {
             Index::CommandLine(idx) => idx.alter_record(name, version, func),
+            Index::Git2(idx) => idx.alter_record(name, version, func),
         }
     }
 
     fn yank_record(&self, name: &str, version: Version) -> Result<(), Error> {
         match self {
             Index::CommandLine(idx) => idx.yank_record(name, version),
+            Index::Git2(idx) => idx.yank_record(name, version),
         }
     }
 
     fn unyank_record(&self, name: &str, version: Version) -> Result<(), Error> {
         match self {
             Index::CommandLine(idx) => idx.unyank_record(name, version),
+            Index::Git2(idx) => idx.unyank_record(name, version),
         }
     }
 }
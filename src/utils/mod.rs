@@ -0,0 +1,2 @@
+/// Helpers to build up Tide [`Response`](tide::Response)s.
+pub mod response;
@@ -0,0 +1,18 @@
+use http::StatusCode;
+use json::json;
+use tide::Response;
+
+/// Builds an error [`Response`] out of an HTTP status code and a message.
+pub fn error(status_code: StatusCode, message: String) -> Response {
+    Response::new(status_code.as_u16() as u16).body_json(&json!({ "errors": [{ "detail": message }] })).unwrap()
+}
+
+/// Builds a successful JSON [`Response`] out of a serializable value.
+pub fn json<T>(value: &T) -> Response
+where
+    T: serde::Serialize,
+{
+    Response::new(StatusCode::OK.as_u16() as u16)
+        .body_json(value)
+        .unwrap()
+}